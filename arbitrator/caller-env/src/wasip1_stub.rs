@@ -338,7 +338,7 @@ static TIME_INTERVAL: u64 = 10_000_000;
 
 /// Retrieves the time in ns of the given clock.
 /// Note that in Nitro, all clocks point to the same deterministic counter that advances 10ms whenever
-/// this function is called.
+/// this function is called. The counter starts at the Unix epoch and, like WASI timestamps, is unsigned.
 pub fn clock_time_get<M: MemAccess, E: ExecEnv>(
     mem: &mut M,
     env: &mut E,
@@ -405,3 +405,98 @@ pub fn poll_oneoff<M: MemAccess, E: ExecEnv>(
     }
     ERRNO_INVAL
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::create_pcg;
+    use alloc::{vec, vec::Vec};
+    use rand::RngCore;
+    use rand_pcg::Pcg32;
+
+    struct TestMem(Vec<u8>);
+
+    impl MemAccess for TestMem {
+        fn read_u8(&self, ptr: GuestPtr) -> u8 {
+            u8::from_le_bytes(self.read_fixed(ptr))
+        }
+
+        fn read_u16(&self, ptr: GuestPtr) -> u16 {
+            u16::from_le_bytes(self.read_fixed(ptr))
+        }
+
+        fn read_u32(&self, ptr: GuestPtr) -> u32 {
+            u32::from_le_bytes(self.read_fixed(ptr))
+        }
+
+        fn read_u64(&self, ptr: GuestPtr) -> u64 {
+            u64::from_le_bytes(self.read_fixed(ptr))
+        }
+
+        fn write_u8(&mut self, ptr: GuestPtr, x: u8) {
+            self.write_slice(ptr, &x.to_le_bytes())
+        }
+
+        fn write_u16(&mut self, ptr: GuestPtr, x: u16) {
+            self.write_slice(ptr, &x.to_le_bytes())
+        }
+
+        fn write_u32(&mut self, ptr: GuestPtr, x: u32) {
+            self.write_slice(ptr, &x.to_le_bytes())
+        }
+
+        fn write_u64(&mut self, ptr: GuestPtr, x: u64) {
+            self.write_slice(ptr, &x.to_le_bytes())
+        }
+
+        fn read_slice(&self, ptr: GuestPtr, len: usize) -> Vec<u8> {
+            let ptr = ptr.0 as usize;
+            self.0[ptr..ptr + len].to_vec()
+        }
+
+        fn read_fixed<const N: usize>(&self, ptr: GuestPtr) -> [u8; N] {
+            self.read_slice(ptr, N).try_into().unwrap()
+        }
+
+        fn write_slice(&mut self, ptr: GuestPtr, data: &[u8]) {
+            let ptr = ptr.0 as usize;
+            self.0[ptr..ptr + data.len()].copy_from_slice(data)
+        }
+    }
+
+    struct TestEnv(Pcg32);
+
+    impl ExecEnv for TestEnv {
+        fn advance_time(&mut self, _: u64) {}
+
+        fn get_time(&self) -> u64 {
+            0
+        }
+
+        fn next_rand_u32(&mut self) -> u32 {
+            self.0.next_u32()
+        }
+
+        fn print_string(&mut self, _: &[u8]) {}
+    }
+
+    #[test]
+    fn test_random_get_whole_words() {
+        for len in [4, 8, 12] {
+            let mut mem = TestMem(vec![0; 16]);
+            let mut env = TestEnv(create_pcg());
+            let errno = random_get(&mut mem, &mut env, GuestPtr(0), len);
+            assert_eq!(errno.0, ERRNO_SUCCESS.0);
+
+            // exactly len / 4 words are drawn, with no extra word for the empty tail
+            let mut rng = create_pcg();
+            let words: Vec<u8> = (0..len / 4)
+                .flat_map(|_| rng.next_u32().to_le_bytes())
+                .collect();
+            let len = len as usize;
+            assert_eq!(mem.0[..len], words);
+            assert!(mem.0[len..].iter().all(|&x| x == 0));
+            assert_eq!(env.0.next_u32(), rng.next_u32());
+        }
+    }
+}
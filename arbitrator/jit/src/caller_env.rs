@@ -141,6 +141,7 @@ impl ExecEnv for JitExecEnv<'_> {
 
 pub struct GoRuntimeState {
     /// An increasing clock used when Go asks for time, measured in nanoseconds.
+    /// Starts at the Unix epoch, since WASI timestamps are unsigned and can't precede it.
     pub time: u64,
     /// Deterministic source of random data.
     pub rng: Pcg32,
@@ -0,0 +1,68 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+use arbutil::{Bytes20, Bytes32};
+use eyre::{bail, Result};
+
+/// A parsed delayed inbox message.
+/// The layout is the one Go's `ParseIncomingL1Message` reads and `Serialize` writes.
+/// All integers are big-endian.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(not(test), allow(dead_code))]
+pub struct InboxMessage {
+    /// The L1 message type
+    pub kind: u8,
+    /// The poster, stored left-padded to 32 bytes whose high bytes Go ignores
+    pub sender: Bytes20,
+    /// The L1 block number
+    pub block_number: u64,
+    /// The L1 timestamp
+    pub timestamp: u64,
+    /// The request id, usually the delayed sequence number
+    pub request_id: Bytes32,
+    /// The L1 base fee
+    pub l1_base_fee: Bytes32,
+    /// The L2 message
+    pub data: Vec<u8>,
+}
+
+impl InboxMessage {
+    /// The length of the fixed-size fields preceding the L2 message.
+    pub const HEADER_LEN: usize = 1 + 32 + 8 + 8 + 32 + 32;
+
+    /// Parses a message from the raw bytes served by `wavmio.readDelayedInboxMessage`.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < Self::HEADER_LEN {
+            bail!("inbox message of {} bytes is too short", bytes.len());
+        }
+        let (header, data) = bytes.split_at(Self::HEADER_LEN);
+        let (sender, header) = header[1..].split_at(32);
+
+        let read_u64 = |x: &[u8]| u64::from_be_bytes(x.try_into().unwrap());
+        let read_bytes32 = |x: &[u8]| Bytes32::try_from(x).unwrap();
+        Ok(Self {
+            kind: bytes[0],
+            sender: sender[12..].try_into().unwrap(),
+            block_number: read_u64(&header[..8]),
+            timestamp: read_u64(&header[8..16]),
+            request_id: read_bytes32(&header[16..48]),
+            l1_base_fee: read_bytes32(&header[48..]),
+            data: data.to_vec(),
+        })
+    }
+
+    /// Serializes the message into the raw bytes Go expects.
+    #[cfg(test)]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::HEADER_LEN + self.data.len());
+        bytes.push(self.kind);
+        bytes.extend([0; 12]);
+        bytes.extend(*self.sender);
+        bytes.extend(self.block_number.to_be_bytes());
+        bytes.extend(self.timestamp.to_be_bytes());
+        bytes.extend(*self.request_id);
+        bytes.extend(*self.l1_base_fee);
+        bytes.extend(&self.data);
+        bytes
+    }
+}
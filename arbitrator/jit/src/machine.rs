@@ -2,25 +2,26 @@
 // For license information, see https://github.com/nitro/blob/master/LICENSE
 
 use crate::{
-    arbcompress, caller_env::GoRuntimeState, program, socket, stylus_backend::CothreadHandler,
-    wasip1_stub, wavmio, Opts,
+    arbcompress, caller_env::GoRuntimeState, inbox::InboxMessage, program, socket,
+    stylus_backend::CothreadHandler, wasip1_stub, wavmio, Opts,
 };
 use arbutil::{Bytes32, Color, PreimageType};
 use eyre::{bail, ErrReport, Result, WrapErr};
 use sha3::{Digest, Keccak256};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fs::File,
     io::{self, Write},
     io::{BufReader, BufWriter, ErrorKind, Read},
     net::TcpStream,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
 use thiserror::Error;
 use wasmer::{
     imports, CompilerConfig, Function, FunctionEnv, FunctionEnvMut, Instance, Memory, Module,
-    Pages, RuntimeError, Store,
+    Pages, RuntimeError, Store, WASM_PAGE_SIZE,
 };
 use wasmer_compiler_cranelift::Cranelift;
 
@@ -147,6 +148,22 @@ pub fn create(opts: &Opts, env: WasmEnv) -> (Instance, FunctionEnv<WasmEnv>, Sto
     (instance, func_env, store)
 }
 
+/// Writes the machine's memory to a file for offline analysis.
+/// The format is the page count as a little-endian u64, followed by the raw bytes.
+pub fn dump_memory(memory: &Memory, store: &Store, path: &Path) -> Result<()> {
+    let view = memory.view(store);
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(&(view.size().0 as u64).to_le_bytes())?;
+
+    let mut page = vec![0; WASM_PAGE_SIZE];
+    for offset in (0..view.data_size()).step_by(WASM_PAGE_SIZE) {
+        view.read(offset, &mut page)?;
+        file.write_all(&page)?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum Escape {
     #[error("program exited with status code `{0}`")]
@@ -186,6 +203,7 @@ pub type WasmEnvMut<'a> = FunctionEnvMut<'a, WasmEnv>;
 pub type Inbox = BTreeMap<u64, Vec<u8>>;
 pub type Preimages = BTreeMap<PreimageType, BTreeMap<Bytes32, Vec<u8>>>;
 pub type ModuleAsm = Arc<[u8]>;
+pub type PreimageObserver = Box<dyn FnMut(Bytes32, PreimageType, usize) + Send>;
 
 #[derive(Default)]
 pub struct WasmEnv {
@@ -199,6 +217,10 @@ pub struct WasmEnv {
     pub large_globals: [Bytes32; 2],
     /// An oracle allowing the prover to reverse keccak256
     pub preimages: Preimages,
+    /// An optional callback observing each preimage resolution
+    pub preimage_observer: Option<PreimageObserver>,
+    /// In debug mode, the largest offset the guest has requested in each preimage
+    pub preimage_high_water: BTreeMap<(PreimageType, Bytes32), usize>,
     /// A collection of programs called during the course of execution
     pub module_asms: HashMap<Bytes32, ModuleAsm>,
     /// The sequencer inbox's messages
@@ -214,8 +236,14 @@ pub struct WasmEnv {
 impl WasmEnv {
     pub fn cli(opts: &Opts) -> Result<Self> {
         let mut env = WasmEnv::default();
+        if opts.forks && opts.dump_memory.is_some() {
+            bail!("--dump-memory can't be used with --forks, whose children would share the file");
+        }
         env.process.forks = opts.forks;
         env.process.debug = opts.debug;
+        if let Some(max) = opts.max_preimage_bytes {
+            env.process.max_preimage_bytes = max;
+        }
 
         let mut inbox_position = opts.inbox_position;
         let mut delayed_position = opts.delayed_inbox_position;
@@ -229,6 +257,8 @@ impl WasmEnv {
         for path in &opts.delayed_inbox {
             let mut msg = vec![];
             File::open(path)?.read_to_end(&mut msg)?;
+            InboxMessage::parse(&msg)
+                .wrap_err_with(|| format!("failed to parse {}", path.to_string_lossy()))?;
             env.delayed_messages.insert(delayed_position, msg);
             delayed_position += 1;
         }
@@ -245,6 +275,10 @@ impl WasmEnv {
                     Err(err) => bail!("Failed to parse {filename}: {}", err),
                 }
                 let size = u64::from_le_bytes(size_buf) as usize;
+                let max = env.process.max_preimage_bytes;
+                if size > max {
+                    bail!("Preimage of {size} bytes in {filename} exceeds the {max} byte limit");
+                }
                 let mut buf = vec![0u8; size];
                 file.read_exact(&mut buf)?;
                 preimages.push(buf);
@@ -258,6 +292,23 @@ impl WasmEnv {
             }
         }
 
+        if let Some(path) = &opts.record_preimages {
+            if opts.forks {
+                // each child would record its own validation into the same file
+                bail!("--record-preimages can't be used with --forks");
+            }
+            let mut file = File::create(path)?;
+            let mut seen = BTreeSet::new();
+            env.on_preimage_resolved(move |hash, ty, len| {
+                if seen.insert((ty, hash)) {
+                    let line = format!("{ty:?} {} {len}\n", hex::encode(hash));
+                    if let Err(err) = file.write_all(line.as_bytes()) {
+                        eprintln!("Failed to record preimage: {err}");
+                    }
+                }
+            });
+        }
+
         fn parse_hex(arg: &Option<String>, name: &str) -> Result<Bytes32> {
             match arg {
                 Some(arg) => {
@@ -281,6 +332,32 @@ impl WasmEnv {
         Ok(env)
     }
 
+    /// Registers a callback invoked with the hash, type, and length of each resolved preimage.
+    /// Go reads preimages 32 bytes at a time, so each preimage may be observed many times.
+    /// Used by `--record-preimages` to list the preimages a run depends on.
+    pub fn on_preimage_resolved(
+        &mut self,
+        observer: impl FnMut(Bytes32, PreimageType, usize) + Send + 'static,
+    ) {
+        self.preimage_observer = Some(Box::new(observer));
+    }
+
+    /// Prints how many preimages were read, listing those the guest only read a prefix of.
+    pub fn print_preimage_reads(&self) {
+        let mut partial = 0;
+        for ((ty, hash), &high_water) in &self.preimage_high_water {
+            let len = self.preimages[ty][hash].len();
+            let read = high_water.saturating_add(32).min(len);
+            if read < len {
+                let hash = hex::encode(hash);
+                println!("Read {read} of {len} bytes of {ty:?} preimage {hash}");
+                partial += 1;
+            }
+        }
+        let total = self.preimage_high_water.len();
+        println!("Read {total} preimages, {partial} of them partially.");
+    }
+
     pub fn send_results(&mut self, error: Option<String>, memory_used: Pages) {
         let writer = match &mut self.process.socket {
             Some((writer, _)) => writer,
@@ -326,6 +403,8 @@ pub struct ProcessEnv {
     pub child_timeout: Duration,
     /// Whether the machine has reached the first wavmio instruction
     pub reached_wavmio: bool,
+    /// The largest preimage the machine will accept from any source
+    pub max_preimage_bytes: usize,
 }
 
 impl Default for ProcessEnv {
@@ -337,6 +416,10 @@ impl Default for ProcessEnv {
             timestamp: Instant::now(),
             child_timeout: Duration::from_secs(15),
             reached_wavmio: false,
+            max_preimage_bytes: DEFAULT_MAX_PREIMAGE_BYTES,
         }
     }
 }
+
+/// A generous preimage size cap, far beyond the 128kb blobs that are the largest legitimate ones.
+pub const DEFAULT_MAX_PREIMAGE_BYTES: usize = 64 * 1024 * 1024;
@@ -9,6 +9,7 @@ use structopt::StructOpt;
 
 mod arbcompress;
 mod caller_env;
+mod inbox;
 mod machine;
 mod program;
 mod socket;
@@ -39,6 +40,10 @@ pub struct Opts {
     #[structopt(long)]
     preimages: Option<PathBuf>,
     #[structopt(long)]
+    max_preimage_bytes: Option<usize>,
+    #[structopt(long)]
+    record_preimages: Option<PathBuf>,
+    #[structopt(long)]
     cranelift: bool,
     #[structopt(long)]
     forks: bool,
@@ -46,6 +51,8 @@ pub struct Opts {
     debug: bool,
     #[structopt(long)]
     require_success: bool,
+    #[structopt(long)]
+    dump_memory: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -78,12 +85,14 @@ fn main() -> Result<()> {
         }
     };
 
-    let memory_used = instance
-        .exports
-        .get_memory("memory")
-        .unwrap()
-        .view(&store)
-        .size();
+    let memory = instance.exports.get_memory("memory").unwrap();
+    let memory_used = memory.view(&store).size();
+
+    if let Some(path) = &opts.dump_memory {
+        if let Err(err) = machine::dump_memory(memory, &store, path) {
+            eprintln!("Failed to dump memory to {}: {err}", path.to_string_lossy());
+        }
+    }
 
     let env = env.as_mut(&mut store);
     let user = env.process.socket.is_none();
@@ -103,6 +112,9 @@ fn main() -> Result<()> {
     if opts.debug || !success {
         println!("{message}");
     }
+    if opts.debug {
+        env.print_preimage_reads();
+    }
 
     let error = match success {
         true => None,
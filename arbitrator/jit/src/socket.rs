@@ -41,6 +41,21 @@ pub fn read_bytes<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<u8>, io::Err
     Ok(buf)
 }
 
+/// Like `read_bytes`, but returns `too_long(size)` without allocating if `size` exceeds `max`.
+pub fn read_bytes_max<T: Read, E: From<io::Error>>(
+    reader: &mut BufReader<T>,
+    max: u64,
+    too_long: impl FnOnce(u64) -> E,
+) -> Result<Vec<u8>, E> {
+    let size = read_u64(reader)?;
+    if size > max {
+        return Err(too_long(size));
+    }
+    let mut buf = vec![0; size as usize];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
 pub fn read_boxed_slice<T: Read>(reader: &mut BufReader<T>) -> Result<Box<[u8]>, io::Error> {
     Ok(Vec::into_boxed_slice(read_bytes(reader)?))
 }
@@ -3,7 +3,10 @@
 
 #![cfg(test)]
 
-use eyre::Result;
+use crate::{inbox::InboxMessage, socket};
+use arbutil::Bytes32;
+use eyre::{eyre, Result};
+use std::io::BufReader;
 use wasmer::{imports, Instance, Module, Store, Value};
 
 #[test]
@@ -22,3 +25,42 @@ fn test_crate() -> Result<()> {
     assert_eq!(result[0], Value::I32(43));
     Ok(())
 }
+
+#[test]
+fn test_inbox_message() -> Result<()> {
+    let message = InboxMessage {
+        kind: 12,
+        sender: [0xab; 20].into(),
+        block_number: 0x0102030405060708,
+        timestamp: u64::MAX,
+        request_id: Bytes32::from(7_u64),
+        l1_base_fee: Bytes32::from(1_000_000_000_u64),
+        data: b"hello".to_vec(),
+    };
+    let encoded = message.encode();
+    assert_eq!(encoded.len(), InboxMessage::HEADER_LEN + 5);
+    assert_eq!(&encoded[1..13], &[0; 12]);
+    assert_eq!(&encoded[33..41], &[1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(InboxMessage::parse(&encoded)?, message);
+
+    assert!(InboxMessage::parse(&encoded[..InboxMessage::HEADER_LEN - 1]).is_err());
+    let mut padded_sender = encoded;
+    padded_sender[1] = 1;
+    assert_eq!(InboxMessage::parse(&padded_sender)?, message);
+    Ok(())
+}
+
+#[test]
+fn test_read_bytes_max() -> Result<()> {
+    let mut stream = 5_u64.to_be_bytes().to_vec();
+    stream.extend(b"hello");
+    let bytes = socket::read_bytes_max(&mut BufReader::new(&stream[..]), 5, |_| eyre!("long"))?;
+    assert_eq!(bytes, b"hello");
+
+    // would abort on allocation if the length were trusted
+    let stream = u64::MAX.to_be_bytes();
+    let reader = &mut BufReader::new(&stream[..]);
+    let err = socket::read_bytes_max(reader, 1024, |size| eyre!("{size}")).unwrap_err();
+    assert_eq!(err.to_string(), u64::MAX.to_string());
+    Ok(())
+}
@@ -192,6 +192,17 @@ pub fn resolve_preimage_impl(
     let len = std::cmp::min(32, preimage.len().saturating_sub(offset));
     let read = preimage.get(offset..(offset + len)).unwrap_or_default();
     mem.write_slice(out_ptr, read);
+
+    if exec.process.debug {
+        let high_water = exec
+            .preimage_high_water
+            .entry((preimage_type, hash))
+            .or_default();
+        *high_water = (*high_water).max(offset);
+    }
+    if let Some(observer) = &mut exec.preimage_observer {
+        observer(hash, preimage_type, preimage.len());
+    }
     Ok(read.len() as u32)
 }
 
@@ -280,7 +291,13 @@ fn ready_hostio(env: &mut WasmEnv) -> MaybeEscape {
         let preimage_count = socket::read_u32(stream)?;
         for _ in 0..preimage_count {
             let hash = socket::read_bytes32(stream)?;
-            let preimage = socket::read_bytes(stream)?;
+            let max = env.process.max_preimage_bytes as u64;
+            let preimage = socket::read_bytes_max(stream, max, |size| {
+                Escape::Failure(format!(
+                    "preimage {} of {size} bytes exceeds the {max} byte limit",
+                    hex::encode(hash)
+                ))
+            })?;
             map.insert(hash, preimage);
         }
     }